@@ -1,107 +1,364 @@
 use rand::thread_rng;
 use rand::seq::SliceRandom;
+use rand::{SeedableRng, rngs::StdRng};
+use num_rational::BigRational;
+use num_traits::{Zero, One};
 use std::fmt;
 use std::error::Error;
 use std::hash::Hash;
+use std::ops::{Add, Sub, Mul, Div};
+use std::iter::Sum;
 use std::collections::{HashMap, HashSet};
+use std::io::{self, Read, Write};
 
-pub struct RoundIterator<'a, T: Eq + Hash + fmt::Debug> {
-    curr_round: PollRound<'a, T>,
+/// A numeric type usable for ballot weights and candidate tallies. Implemented
+/// for `f64` (fast, approximate) and `num_rational::BigRational` (exact,
+/// suitable for close counts where floating point could corrupt a surplus
+/// transfer).
+pub trait Number:
+    Clone
+    + PartialOrd
+    + fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Sum
+{
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_usize(n: usize) -> Self;
+    /// Round down to the nearest integer, used for Droop quota calculations.
+    fn floor(self) -> Self;
 }
 
-impl<'a, T: Eq + Hash + fmt::Debug> Iterator for RoundIterator<'a, T> {
-    type Item = PollResult<'a, T>;
+impl Number for f64 {
+    fn zero() -> Self { 0.0 }
+    fn one() -> Self { 1.0 }
+    fn from_usize(n: usize) -> Self { n as f64 }
+    fn floor(self) -> Self { f64::floor(self) }
+}
+
+impl Number for BigRational {
+    fn zero() -> Self { <Self as Zero>::zero() }
+    fn one() -> Self { <Self as One>::one() }
+    fn from_usize(n: usize) -> Self { Self::from_integer(n.into()) }
+    fn floor(self) -> Self { BigRational::floor(&self) }
+}
+
+/// How to resolve a tie between candidates sharing the lowest tally in a round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieStrategy {
+    /// Compare the tied candidates' tallies in previous rounds, most recent first,
+    /// eliminating whoever was lowest in the first round that distinguishes them.
+    Forward,
+    /// Compare the tied candidates' tallies in previous rounds, earliest first,
+    /// eliminating whoever was lowest in the first round that distinguishes them.
+    Backward,
+    /// Pick uniformly at random using a seeded, reproducible RNG.
+    Random(u64),
+}
+
+/// Which tie-break actually resolved a tie, surfaced in `PollResult` for
+/// transparency (a `Forward`/`Backward` strategy may still fall back to
+/// `Random` if the tied candidates match in every prior round).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    Forward,
+    Backward,
+    Random,
+}
+
+/// Find the candidate(s) tied for the lowest value in `results`.
+fn lowest_candidates<'a, T: Eq + Hash + fmt::Debug, N: Number>(results: &HashMap<&'a T, N>) -> (N, Vec<&'a T>) {
+    let lowest = results.values()
+        .cloned()
+        .reduce(|a, b| if b < a { b } else { a })
+        .unwrap();
+    let tied = results.iter()
+        .filter(|&(_, v)| *v == lowest)
+        .map(|(&k, _)| k)
+        .collect();
+    (lowest, tied)
+}
+
+/// Resolve a tie among `tied` candidates using `strategy`, consulting `history`
+/// (earliest round first) for `Forward`/`Backward`. Returns the candidate to
+/// eliminate and the tie-break that was actually applied.
+fn resolve_tie<'a, T: Eq + Hash + fmt::Debug, N: Number>(
+    tied: &[&'a T],
+    history: &[HashMap<&'a T, N>],
+    strategy: TieStrategy,
+) -> (&'a T, TieBreak) {
+    let by_earliest_distinguishing_round = |rounds: &mut dyn Iterator<Item = &HashMap<&'a T, N>>| {
+        for round_results in rounds {
+            let mut lowest: Option<N> = None;
+            let mut candidate = None;
+            let mut unique = true;
+            for &c in tied {
+                let v = round_results.get(c).unwrap().clone();
+                match &lowest {
+                    None => {
+                        candidate = Some(c);
+                        unique = true;
+                        lowest = Some(v);
+                    },
+                    Some(lw) if v < *lw => {
+                        candidate = Some(c);
+                        unique = true;
+                        lowest = Some(v);
+                    },
+                    Some(lw) if v == *lw => {
+                        unique = false;
+                    },
+                    _ => {},
+                }
+            }
+            if unique {
+                return candidate;
+            }
+        }
+        None
+    };
+
+    match strategy {
+        TieStrategy::Backward => {
+            if let Some(c) = by_earliest_distinguishing_round(&mut history.iter()) {
+                return (c, TieBreak::Backward);
+            }
+            (random_tied_candidate(tied, seed_of(strategy)), TieBreak::Random)
+        },
+        TieStrategy::Forward => {
+            if let Some(c) = by_earliest_distinguishing_round(&mut history.iter().rev()) {
+                return (c, TieBreak::Forward);
+            }
+            (random_tied_candidate(tied, seed_of(strategy)), TieBreak::Random)
+        },
+        TieStrategy::Random(seed) => (random_tied_candidate(tied, seed), TieBreak::Random),
+    }
+}
+
+fn seed_of(strategy: TieStrategy) -> u64 {
+    match strategy {
+        TieStrategy::Random(seed) => seed,
+        _ => 0,
+    }
+}
+
+fn random_tied_candidate<'a, T: Eq + Hash + fmt::Debug>(tied: &[&'a T], seed: u64) -> &'a T {
+    let mut rng = StdRng::seed_from_u64(seed);
+    // `tied` arrives in `HashMap` iteration order, which differs between runs;
+    // put it in a fixed order first so the same seed always picks the same candidate.
+    let mut ordered = tied.to_vec();
+    ordered.sort_by_cached_key(|c| format!("{:?}", c));
+    // `tied` is never empty: it is built from the candidates sharing the lowest tally.
+    ordered.choose(&mut rng).copied().unwrap()
+}
+
+/// A candidate interned to a small integer so ballots can be stored and
+/// tallied as `usize`s instead of pointers into the candidate set.
+type CandidateIndex = usize;
+
+/// A distinct preference ordering, interned to candidate indices, together
+/// with the combined weight of every ballot cast with exactly this ordering
+/// (for unweighted ballots this is simply how many were cast: identical
+/// ballots are merged on insertion rather than stored one-by-one). `active`
+/// is a cursor into `prefs`: preferences before it name candidates already
+/// eliminated in an earlier round, and are skipped in place rather than
+/// copied into a new, shorter vector every round.
+#[derive(Clone)]
+struct BallotForm<N> {
+    prefs: Vec<CandidateIndex>,
+    active: usize,
+    weight: N,
+}
+
+impl<N: Number> BallotForm<N> {
+    /// The candidate this ballot currently counts toward, or `None` if every
+    /// preference it ranked has been eliminated (it has exhausted).
+    fn current(&self) -> Option<CandidateIndex> {
+        self.prefs.get(self.active).copied()
+    }
+}
+
+pub struct RoundIterator<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
+    curr_round: PollRound<'a, T, N>,
+}
+
+impl<'a, T: Eq + Hash + fmt::Debug, N: Number> Iterator for RoundIterator<'a, T, N> {
+    type Item = RoundOutcome<'a, T, N>;
 
     fn next(&mut self) -> Option<Self::Item> {
         let round = self.curr_round.next_round();
         match round {
-            Some((result, r)) => {
+            Some((outcome, r)) => {
                 self.curr_round = r;
-                Some(result)
+                Some(outcome)
             },
             None => None
         }
     }
 }
 
-pub struct PollResult<'a, T: Eq + Hash + fmt::Debug> {
+/// The result of tallying a single round: either a candidate is eliminated
+/// and counting continues, or a candidate holds a strict majority of the
+/// continuing votes and is declared the winner, ending the count.
+pub enum RoundOutcome<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
+    Eliminated(PollResult<'a, T, N>),
+    Winner {
+        candidate: &'a T,
+        votes: N,
+        round: usize,
+    },
+}
+
+pub struct PollResult<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
     pub loser: &'a T,
-    pub votes: usize,
-    pub results: HashMap<&'a T, usize>,
+    pub votes: N,
+    pub results: HashMap<&'a T, N>,
     pub round: usize,
+    /// Combined weight of ballots that have no remaining preference (all
+    /// ranked candidates have been eliminated), accumulated up to and
+    /// including this round.
+    pub exhausted: N,
+    /// Which tie-break, if any, was applied to choose `loser` among
+    /// candidates sharing the lowest tally this round.
+    pub tie_break: Option<TieBreak>,
 }
-pub struct PollRound<'a, T: Eq + Hash + fmt::Debug> {
-    candidates: Vec<&'a T>,
-    ballots: Vec<Vec<&'a T>>,
+pub struct PollRound<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
+    /// Interning table shared with the originating `Poll`: index -> candidate.
+    index_candidates: Vec<&'a T>,
+    /// Interning table shared with the originating `Poll`: candidate -> index.
+    candidate_index: HashMap<&'a T, CandidateIndex>,
+    /// Per-candidate elimination flag, indexed by `CandidateIndex`.
+    eliminated: Vec<bool>,
+    /// Count of candidates not yet eliminated; zero means counting is done.
+    remaining: usize,
+    ballots: Vec<BallotForm<N>>,
     last_round: usize,
+    /// Per-round tallies from every previous round, earliest first, kept so
+    /// `Forward`/`Backward` tie-breaks can look back through the count.
+    history: Vec<HashMap<&'a T, N>>,
+    tie_strategy: TieStrategy,
 }
-impl<'a, 'b, T: Eq + Hash + fmt::Debug> PollRound<'a, T> {
-    pub fn first_round(poll: &'b Poll<'a, T>) -> RoundIterator<'a, T> {
-        let ballots = poll.ballots.clone();
+impl<'a, 'b, T: Eq + Hash + fmt::Debug, N: Number> PollRound<'a, T, N> {
+    pub fn first_round(poll: &'b Poll<'a, T, N>) -> RoundIterator<'a, T, N> {
+        let remaining = poll.index_candidates.len();
         RoundIterator {
             curr_round: PollRound {
-                candidates: poll.candidates.iter().collect(),
-                ballots,
-                last_round: 0
+                index_candidates: poll.index_candidates.clone(),
+                candidate_index: poll.candidate_index.clone(),
+                eliminated: vec![false; remaining],
+                remaining,
+                ballots: poll.ballots.clone(),
+                last_round: 0,
+                history: Vec::new(),
+                tie_strategy: poll.tie_strategy,
             }
         }
     }
-    fn next_round(&self) -> Option<(PollResult<'a, T>, Self)> {
-        let mut results = HashMap::new();
-        let mut next_ballots: Vec<Vec<&'a T>> = Vec::new();
-        
-        for &i in &self.candidates {
-            results.insert(i, 0);
+    fn next_round(&self) -> Option<(RoundOutcome<'a, T, N>, Self)> {
+        // Once every candidate has been eliminated there's nothing left to count.
+        if self.remaining == 0 {
+            return None;
         }
-        
+
+        // A flat Vec indexed by CandidateIndex, rather than a HashMap, since
+        // every candidate (eliminated or not) has a stable slot to add into.
+        let mut tallies = vec![N::zero(); self.index_candidates.len()];
+        let mut exhausted = N::zero();
+
         for ballot in &self.ballots {
             // Ballot should have been validated in Poll::add_ballot.
-            // All ballots have the same amount of candidates, so if this one does not have a first candidate,
-            //   then all of them are empty and we're done the vote
-            let &vote = match ballot.first() {
-                Some(v) => v,
-                None => return None,
-            };
-            // Since Poll::add_ballot already verified that this will never fail, panic if it does (indicates a bug in Poll::add_ballot).
-            let vote_box = results.get_mut(vote).unwrap();
-            *vote_box += 1;
-        }
-        
-        let mut lowest = None;
-        let mut loser = None;
-        
-        for (&k, &v) in &results {
-            if lowest.is_none() {
-                lowest = Some(v);
-                loser = Some(k);
-                continue;
+            // A ballot with no remaining preference (either because it was cast
+            //   truncated, or because all of its ranked candidates have since
+            //   been eliminated) is exhausted and no longer contributes to any
+            //   candidate's tally.
+            match ballot.current() {
+                Some(i) => {
+                    tallies[i] = tallies[i].clone() + ballot.weight.clone();
+                },
+                None => {
+                    exhausted = exhausted + ballot.weight.clone();
+                },
             }
-            let lw = lowest.unwrap();
-            if v < lw {
-                lowest = Some(v);
-                loser = Some(k);
+        }
+
+        let results: HashMap<&'a T, N> = (0..self.index_candidates.len())
+            .filter(|&i| !self.eliminated[i])
+            .map(|i| (self.index_candidates[i], tallies[i].clone()))
+            .collect();
+
+        // A candidate holding a strict majority of the continuing (non-exhausted)
+        // votes has won outright; there's no need to keep eliminating.
+        let continuing: N = results.values().cloned().sum();
+        for (&k, v) in &results {
+            if continuing > N::zero() && v.clone() * N::from_usize(2) > continuing.clone() {
+                return Some((
+                    RoundOutcome::Winner {
+                        candidate: k,
+                        votes: v.clone(),
+                        round: self.last_round + 1,
+                    },
+                    Self {
+                        index_candidates: self.index_candidates.clone(),
+                        candidate_index: self.candidate_index.clone(),
+                        eliminated: vec![true; self.index_candidates.len()],
+                        remaining: 0,
+                        ballots: Vec::new(),
+                        last_round: self.last_round + 1,
+                        history: Vec::new(),
+                        tie_strategy: self.tie_strategy,
+                    }
+                ));
             }
         }
-        
-        let loser = loser.unwrap();
-        let lowest = lowest.unwrap();
-        
-        for ballot in &self.ballots {
-            let new_ballot = ballot.iter().filter(|&&i| i != loser).map(|&i| i).collect();
-            next_ballots.push(new_ballot);
+
+        let (lowest, tied) = lowest_candidates(&results);
+        let (loser, tie_break) = if tied.len() == 1 {
+            (tied[0], None)
+        } else {
+            let (loser, applied) = resolve_tie(&tied, &self.history, self.tie_strategy);
+            (loser, Some(applied))
+        };
+
+        let mut eliminated = self.eliminated.clone();
+        eliminated[self.candidate_index[loser]] = true;
+
+        // Advance each ballot's active-preference cursor past the newly
+        // eliminated candidate in place, rather than rebuilding its
+        // preference list.
+        let mut next_ballots = self.ballots.clone();
+        for ballot in &mut next_ballots {
+            while let Some(&i) = ballot.prefs.get(ballot.active) {
+                if !eliminated[i] {
+                    break;
+                }
+                ballot.active += 1;
+            }
         }
-        
+
+        let mut history = self.history.clone();
+        history.push(results.clone());
+
         Some((
-            PollResult {
+            RoundOutcome::Eliminated(PollResult {
                 loser,
                 votes: lowest,
             results,
             round: self.last_round + 1,
-            },
+            exhausted,
+            tie_break,
+            }),
             Self {
-                candidates: self.candidates.iter().filter(|&&i| i != loser).map(|&i| i).collect(),
+                index_candidates: self.index_candidates.clone(),
+                candidate_index: self.candidate_index.clone(),
+                eliminated,
+                remaining: self.remaining - 1,
                 ballots: next_ballots,
-                last_round: self.last_round + 1
+                last_round: self.last_round + 1,
+                history,
+                tie_strategy: self.tie_strategy,
             }
         ))
     }
@@ -124,32 +381,68 @@ impl<'a, T: fmt::Display> fmt::Display for BallotError<'a, T> {
 }
 impl<'a, T: fmt::Debug + fmt::Display> Error for BallotError<'a, T> {}
 
-pub struct Poll<'a, T: Eq + Hash + fmt::Debug> {
+pub struct Poll<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
     candidates: &'a HashSet<T>,
-    ballots: Vec<Vec<&'a T>>,
+    /// Interning table: index -> candidate.
+    index_candidates: Vec<&'a T>,
+    /// Interning table: candidate -> index.
+    candidate_index: HashMap<&'a T, CandidateIndex>,
+    /// Distinct preference orderings cast so far, interned and aggregated by
+    /// weight; see `BallotForm`. Indexed by `ballot_forms`, so identical
+    /// ballots can be merged in `add_ballot` instead of stored one-by-one.
+    ballots: Vec<BallotForm<N>>,
+    ballot_forms: HashMap<Vec<CandidateIndex>, usize>,
     candidate_map: HashMap<&'a T, bool>,
+    /// When `true`, `add_ballot` accepts ballots that rank only a subset of
+    /// the candidates instead of requiring every candidate to appear.
+    truncated: bool,
+    /// How to resolve ties for lowest tally between candidates in a round.
+    tie_strategy: TieStrategy,
 }
-impl<'a, T: Eq + Hash + fmt::Debug> Poll<'a, T> {
+impl<'a, T: Eq + Hash + fmt::Debug, N: Number> Poll<'a, T, N> {
     pub fn new(candidates: &'a HashSet<T>) -> Self {
         let mut candidate_map: HashMap<&'a T, bool> = HashMap::new();
+        let index_candidates: Vec<&'a T> = candidates.iter().collect();
+        let candidate_index: HashMap<&'a T, CandidateIndex> = index_candidates.iter()
+            .enumerate()
+            .map(|(i, &c)| (c, i))
+            .collect();
 
         for i in candidates {
             candidate_map.insert(i, false);
         }
 
         Self {
-            candidates: candidates,
+            candidates,
+            index_candidates,
+            candidate_index,
             ballots: Vec::new(),
+            ballot_forms: HashMap::new(),
             candidate_map,
+            truncated: false,
+            tie_strategy: TieStrategy::Forward,
         }
     }
+    /// Allow (or forbid) ballots that don't rank every candidate. Such a
+    /// ballot becomes exhausted once every candidate it ranked has been
+    /// eliminated, rather than continuing to contribute to the count.
+    pub fn allow_truncated(mut self, truncated: bool) -> Self {
+        self.truncated = truncated;
+        self
+    }
+    /// Set the strategy used to break ties for lowest tally in a round.
+    pub fn tie_strategy(mut self, strategy: TieStrategy) -> Self {
+        self.tie_strategy = strategy;
+        self
+    }
     pub fn generate_ballot(&self) -> Vec<&'a T> {
         let mut vec: Vec<&T> = self.candidates.iter().collect();
         // Randomize ballot to negate ballot order effect
         vec.shuffle(&mut thread_rng());
         vec
     }
-    pub fn add_ballot(&mut self, ballot: Vec<&'a T>) -> Result<(), BallotError<'a, T>> {
+    /// Add a ballot to the poll, weighted by `weight` (or one vote, if `None`).
+    pub fn add_ballot(&mut self, ballot: Vec<&'a T>, weight: Option<N>) -> Result<(), BallotError<'a, T>> {
         // A hashmap is used so that we can verify all the candidates provided are in the poll,
         // that we don't have any duplicate candidates, and that there are no missing candidates
         let mut hm = self.candidate_map.clone();
@@ -167,24 +460,723 @@ impl<'a, T: Eq + Hash + fmt::Debug> Poll<'a, T> {
             *available = true;
         }
 
-        for (&k, v) in &hm {
-            if !*v {
-                // Candidate wasn't included in the ballot
-                return Err(BallotError::MissingCandidate(k));
+        if !self.truncated {
+            for (&k, v) in &hm {
+                if !*v {
+                    // Candidate wasn't included in the ballot
+                    return Err(BallotError::MissingCandidate(k));
+                }
             }
         }
-        
-        // Ballot is verified
-        self.ballots.push(ballot);
+
+        // Ballot is verified. Intern it to candidate indices and merge it
+        // into any existing ballot form with the exact same ordering, rather
+        // than storing another copy of an equivalent preference list.
+        let prefs: Vec<CandidateIndex> = ballot.iter().map(|&v| self.candidate_index[v]).collect();
+        let weight = weight.unwrap_or_else(N::one);
+        match self.ballot_forms.get(&prefs) {
+            Some(&i) => {
+                let form = &mut self.ballots[i];
+                form.weight = form.weight.clone() + weight;
+            },
+            None => {
+                self.ballot_forms.insert(prefs.clone(), self.ballots.len());
+                self.ballots.push(BallotForm { prefs, active: 0, weight });
+            },
+        }
 
         Ok(())
     }
-    pub fn start_rounds<'b>(&self) -> RoundIterator<'a, T> {
+    pub fn start_rounds(&self) -> RoundIterator<'a, T, N> {
         PollRound::first_round(self)
     }
+    /// Run rounds until a candidate reaches a majority, returning the winner
+    /// alongside the full round-by-round trace leading up to them. Returns
+    /// `None` if every candidate is eliminated without anyone ever reaching
+    /// a majority (e.g. all ballots exhaust beforehand).
+    pub fn winner(&self) -> (Option<&'a T>, Vec<RoundOutcome<'a, T, N>>) {
+        let mut trace = Vec::new();
+        let mut winner = None;
+
+        for outcome in self.start_rounds() {
+            if let RoundOutcome::Winner { candidate, .. } = &outcome {
+                winner = Some(*candidate);
+            }
+            trace.push(outcome);
+            if winner.is_some() {
+                break;
+            }
+        }
+
+        (winner, trace)
+    }
 }
-impl<'a, T: Eq + Hash + fmt::Debug> From<&'a HashSet<T>> for Poll<'a, T> {
+impl<'a, T: Eq + Hash + fmt::Debug, N: Number> From<&'a HashSet<T>> for Poll<'a, T, N> {
     fn from(candidates: &'a HashSet<T>) -> Self {
         Self::new(candidates)
     }
 }
+
+/// Which surplus-transfer rule `Poll::count_stv` applies when a candidate is
+/// elected with more votes than the quota.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StvTransferMethod {
+    /// Only the parcel of ballots most recently received by the candidate is
+    /// redistributed, at `surplus / parcel_size`; earlier parcels stay with
+    /// the candidate permanently.
+    LastParcelGregory,
+    /// Every ballot currently held by the candidate is redistributed, at
+    /// `surplus / total_transferable_votes`.
+    WeightedInclusiveGregory,
+}
+
+/// The outcome of a single STV counting round.
+pub struct StvRoundResult<'a, T: Eq + Hash + fmt::Debug, N: Number = f64> {
+    pub round: usize,
+    /// Continuing candidates' tallies as of this round.
+    pub tallies: HashMap<&'a T, N>,
+    /// Candidates elected this round (by reaching quota, or by filling the
+    /// remaining seats once continuing candidates equal remaining seats).
+    pub elected: Vec<&'a T>,
+    /// Candidate eliminated this round, if no one reached quota.
+    pub eliminated: Option<&'a T>,
+    pub quota: N,
+    /// Each candidate's keep value after this round, only populated by
+    /// `StvMethod::Meek`.
+    pub keep_values: Option<HashMap<&'a T, N>>,
+    /// Combined weight of ballots with no remaining preference, only
+    /// populated by `StvMethod::Meek`.
+    pub exhausted: Option<N>,
+    /// Which tie-break, if any, was applied to choose `eliminated` among
+    /// candidates sharing the lowest tally this round.
+    pub tie_break: Option<TieBreak>,
+}
+
+/// Which STV counting method `Poll::count_stv` applies.
+pub enum StvMethod<N: Number = f64> {
+    /// Gregory surplus transfer: an elected candidate's surplus moves on at a
+    /// fractional transfer value, with `StvTransferMethod` choosing whether
+    /// only the most recent parcel or every ballot they hold is transferred.
+    Gregory(StvTransferMethod),
+    /// Every ballot is re-examined in full each round against a per-candidate
+    /// keep value, iteratively adjusted until elected candidates' tallies sit
+    /// within `tolerance` of the quota.
+    Meek { tolerance: N },
+}
+
+/// A distinct ballot as tracked during STV counting: its remaining
+/// preferences (nearest first; leading preferences that are no longer
+/// continuing are dropped at the start of each round) and current
+/// transfer weight. `arrived` is the round in which the ballot's current
+/// first preference was assigned, used to find a candidate's most recently
+/// received parcel under `StvTransferMethod::LastParcelGregory`.
+struct StvBallot<'a, T, N> {
+    prefs: Vec<&'a T>,
+    weight: N,
+    arrived: usize,
+}
+
+impl<'a, T: Eq + Hash + fmt::Debug, N: Number> Poll<'a, T, N> {
+    /// Count a multi-seat election using the Single Transferable Vote with a
+    /// Droop quota, returning the elected candidates and the round-by-round
+    /// trace. Stops once every seat is filled, or once the number of
+    /// continuing candidates equals the remaining seats (in which case they
+    /// are all elected without a further quota check).
+    pub fn count_stv(&self, seats: usize, method: StvMethod<N>) -> (Vec<&'a T>, Vec<StvRoundResult<'a, T, N>>) {
+        match method {
+            StvMethod::Gregory(transfer) => self.count_stv_gregory(seats, transfer),
+            StvMethod::Meek { tolerance } => self.count_stv_meek(seats, tolerance),
+        }
+    }
+
+    /// Gregory surplus transfer (`StvMethod::Gregory`): ballots are grouped
+    /// into parcels by the round they're received in, and once a candidate is
+    /// elected the ballots `method` selects move on at a reduced value.
+    fn count_stv_gregory(&self, seats: usize, method: StvTransferMethod) -> (Vec<&'a T>, Vec<StvRoundResult<'a, T, N>>) {
+        let total_valid_votes: N = self.ballots.iter().map(|b| b.weight.clone()).sum();
+        let quota = (total_valid_votes / N::from_usize(seats + 1)).floor() + N::one();
+
+        let mut continuing: Vec<&'a T> = self.candidates.iter().collect();
+        let mut elected: Vec<&'a T> = Vec::new();
+        let mut ballots: Vec<StvBallot<'a, T, N>> = self.ballots.iter()
+            .map(|b| StvBallot {
+                prefs: b.prefs[b.active..].iter().map(|&i| self.index_candidates[i]).collect(),
+                weight: b.weight.clone(),
+                arrived: 0,
+            })
+            .collect();
+        let mut trace = Vec::new();
+        let mut round = 0;
+        // Earlier rounds' tallies, earliest first, consulted by `resolve_tie`
+        // for `TieStrategy::Forward`/`Backward`; mirrors `PollRound::history`.
+        let mut history: Vec<HashMap<&'a T, N>> = Vec::new();
+
+        while elected.len() < seats && !continuing.is_empty() {
+            round += 1;
+
+            // Drop leading preferences that are no longer continuing (already
+            // elected or eliminated in an earlier round).
+            for ballot in &mut ballots {
+                while let Some(&pref) = ballot.prefs.first() {
+                    if continuing.contains(&pref) {
+                        break;
+                    }
+                    ballot.prefs.remove(0);
+                }
+            }
+
+            let mut tallies: HashMap<&'a T, N> = continuing.iter().map(|&c| (c, N::zero())).collect();
+            for ballot in &ballots {
+                if let Some(&pref) = ballot.prefs.first() {
+                    let entry = tallies.get_mut(pref).unwrap();
+                    *entry = entry.clone() + ballot.weight.clone();
+                }
+            }
+
+            let remaining_seats = seats - elected.len();
+            if continuing.len() <= remaining_seats {
+                let newly_elected = continuing.clone();
+                elected.extend(newly_elected.iter().copied());
+                history.push(tallies.clone());
+                trace.push(StvRoundResult { round, tallies, elected: newly_elected, eliminated: None, quota: quota.clone(), keep_values: None, exhausted: None, tie_break: None });
+                break;
+            }
+
+            let mut reaching_quota: Vec<&'a T> = continuing.iter()
+                .copied()
+                .filter(|c| *tallies.get(c).unwrap() >= quota)
+                .collect();
+            // Elect the highest tallies first when several candidates cross
+            // quota in the same round, so a seat shortfall falls on whoever
+            // cleared quota by the smallest margin.
+            reaching_quota.sort_by(|a, b| tallies.get(b).unwrap().partial_cmp(tallies.get(a).unwrap()).unwrap());
+
+            if !reaching_quota.is_empty() {
+                let mut newly_elected = Vec::new();
+                for candidate in reaching_quota {
+                    if elected.len() >= seats {
+                        break;
+                    }
+                    let votes = tallies.get(&candidate).unwrap().clone();
+                    let surplus = votes.clone() - quota.clone();
+                    continuing.retain(|&c| c != candidate);
+                    elected.push(candidate);
+                    newly_elected.push(candidate);
+
+                    if surplus > N::zero() && elected.len() < seats {
+                        match method {
+                            StvTransferMethod::WeightedInclusiveGregory => {
+                                let factor = surplus.clone() / votes;
+                                for ballot in &mut ballots {
+                                    if ballot.prefs.first() == Some(&candidate) {
+                                        ballot.weight = ballot.weight.clone() * factor.clone();
+                                        ballot.prefs.remove(0);
+                                        ballot.arrived = round;
+                                    }
+                                }
+                            },
+                            StvTransferMethod::LastParcelGregory => {
+                                let last_parcel_round = round - 1;
+                                let parcel_size: N = ballots.iter()
+                                    .filter(|b| b.prefs.first() == Some(&candidate) && b.arrived == last_parcel_round)
+                                    .map(|b| b.weight.clone())
+                                    .sum();
+                                if parcel_size > N::zero() {
+                                    let factor = surplus.clone() / parcel_size;
+                                    for ballot in &mut ballots {
+                                        if ballot.prefs.first() == Some(&candidate) && ballot.arrived == last_parcel_round {
+                                            ballot.weight = ballot.weight.clone() * factor.clone();
+                                            ballot.prefs.remove(0);
+                                            ballot.arrived = round;
+                                        }
+                                    }
+                                }
+                            },
+                        }
+                    }
+
+                    // Any ballots still sitting with the now-elected candidate were
+                    // not part of the transferred parcel: they're locked in as
+                    // spent and drop out of further counting.
+                    ballots.retain(|b| b.prefs.first() != Some(&candidate));
+                }
+                history.push(tallies.clone());
+                trace.push(StvRoundResult { round, tallies, elected: newly_elected, eliminated: None, quota: quota.clone(), keep_values: None, exhausted: None, tie_break: None });
+                continue;
+            }
+
+            // No one reached quota: eliminate the lowest continuing candidate
+            // and transfer their ballots onward at full value.
+            let (_, tied) = lowest_candidates(&tallies);
+            let (loser, tie_break) = if tied.len() == 1 {
+                (tied[0], None)
+            } else {
+                let (loser, applied) = resolve_tie(&tied, &history, self.tie_strategy);
+                (loser, Some(applied))
+            };
+            continuing.retain(|&c| c != loser);
+            for ballot in &mut ballots {
+                if ballot.prefs.first() == Some(&loser) {
+                    ballot.prefs.remove(0);
+                    ballot.arrived = round;
+                }
+            }
+            history.push(tallies.clone());
+            trace.push(StvRoundResult { round, tallies, elected: Vec::new(), eliminated: Some(loser), quota: quota.clone(), keep_values: None, exhausted: None, tie_break });
+        }
+
+        (elected, trace)
+    }
+
+    /// Meek method (`StvMethod::Meek`): every candidate has a keep value `k`
+    /// in `[0, 1]`, and every ballot is re-examined in full each round,
+    /// handing each continuing preference `weight * k` and passing the
+    /// remainder on to the next preference at `weight * (1 - k)`. Elected
+    /// candidates' keep values are iteratively adjusted so their tally
+    /// converges on the quota (recomputed each round from the non-exhausted
+    /// total) to within `tolerance`, before the next elimination is decided.
+    fn count_stv_meek(&self, seats: usize, tolerance: N) -> (Vec<&'a T>, Vec<StvRoundResult<'a, T, N>>) {
+        /// Cap on keep-value recomputations within a single round; see the
+        /// loop below for why convergence isn't otherwise guaranteed.
+        const MAX_KEEP_VALUE_ITERATIONS: u32 = 1_000;
+
+        let candidates: Vec<&'a T> = self.candidates.iter().collect();
+        let total_weight: N = self.ballots.iter().map(|b| b.weight.clone()).sum();
+
+        let mut keep: HashMap<&'a T, N> = candidates.iter().map(|&c| (c, N::one())).collect();
+        let mut elected: Vec<&'a T> = Vec::new();
+        let mut eliminated_count = 0;
+        let mut trace = Vec::new();
+        let mut round = 0;
+        // Earlier rounds' tallies, earliest first, consulted by `resolve_tie`
+        // for `TieStrategy::Forward`/`Backward`; mirrors `PollRound::history`.
+        let mut history: Vec<HashMap<&'a T, N>> = Vec::new();
+
+        // Given the current keep values, distribute every ballot's weight
+        // across its preferences (continuing candidates get `weight * k` of
+        // whatever reaches them; the rest carries on to the next preference).
+        // A candidate's keep value is driven to zero as soon as they're
+        // eliminated, so their share simply passes through to the next
+        // preference without any special-casing here. Returns each
+        // candidate's tally plus the total exhausted weight.
+        let distribute = |keep: &HashMap<&'a T, N>| -> (HashMap<&'a T, N>, N) {
+            let mut tallies: HashMap<&'a T, N> = candidates.iter().map(|&c| (c, N::zero())).collect();
+            let mut exhausted = N::zero();
+            for form in &self.ballots {
+                let mut weight = form.weight.clone();
+                for &pref_idx in &form.prefs[form.active..] {
+                    if weight <= N::zero() {
+                        break;
+                    }
+                    let pref = self.index_candidates[pref_idx];
+                    let k = keep.get(pref).cloned().unwrap_or_else(N::zero);
+                    if k > N::zero() {
+                        let entry = tallies.get_mut(pref).unwrap();
+                        *entry = entry.clone() + weight.clone() * k.clone();
+                    }
+                    weight = weight * (N::one() - k);
+                }
+                exhausted = exhausted + weight;
+            }
+            (tallies, exhausted)
+        };
+
+        while elected.len() < seats && elected.len() + eliminated_count < candidates.len() {
+            round += 1;
+
+            let mut tallies;
+            let mut exhausted;
+            let mut quota;
+            // `k := k * quota / votes` isn't guaranteed to land within `tolerance`
+            // in a bounded number of steps (a small `tolerance` on the exact
+            // `BigRational` backend, or a pathological ballot set, can keep it
+            // oscillating), so recomputation is capped rather than run until
+            // convergence: past the cap this round's keep values are accepted
+            // as close enough and counting proceeds.
+            let mut keep_iterations = 0;
+            loop {
+                let (t, e) = distribute(&keep);
+                quota = ((total_weight.clone() - e.clone()) / N::from_usize(seats + 1)).floor() + N::one();
+                tallies = t;
+                exhausted = e;
+
+                let mut max_diff: Option<N> = None;
+                for &c in &elected {
+                    let votes = tallies.get(c).unwrap().clone();
+                    let diff = if votes >= quota { votes - quota.clone() } else { quota.clone() - votes };
+                    max_diff = Some(match max_diff {
+                        Some(d) if d > diff => d,
+                        _ => diff,
+                    });
+                }
+                keep_iterations += 1;
+                match &max_diff {
+                    Some(d) if *d > tolerance && keep_iterations < MAX_KEEP_VALUE_ITERATIONS => {
+                        for &c in &elected {
+                            let votes = tallies.get(c).unwrap().clone();
+                            if votes > N::zero() {
+                                let k = keep.get(c).unwrap().clone();
+                                keep.insert(c, k * quota.clone() / votes);
+                            }
+                        }
+                    },
+                    _ => break,
+                }
+            }
+
+            let continuing_tallies: HashMap<&'a T, N> = tallies.iter()
+                .filter(|&(&c, _)| !elected.contains(&c) && *keep.get(c).unwrap() > N::zero())
+                .map(|(&c, v)| (c, v.clone()))
+                .collect();
+
+            let newly_elected: Vec<&'a T> = continuing_tallies.iter()
+                .filter(|&(_, v)| *v >= quota)
+                .map(|(&c, _)| c)
+                .collect();
+
+            if !newly_elected.is_empty() {
+                for &c in &newly_elected {
+                    elected.push(c);
+                }
+                history.push(continuing_tallies.clone());
+                trace.push(StvRoundResult {
+                    round,
+                    tallies: continuing_tallies,
+                    elected: newly_elected,
+                    eliminated: None,
+                    quota,
+                    keep_values: Some(keep.clone()),
+                    exhausted: Some(exhausted),
+                    tie_break: None,
+                });
+                continue;
+            }
+
+            let remaining_seats = seats - elected.len();
+            if continuing_tallies.len() <= remaining_seats {
+                let filled: Vec<&'a T> = continuing_tallies.keys().copied().collect();
+                elected.extend(filled.iter().copied());
+                history.push(continuing_tallies.clone());
+                trace.push(StvRoundResult {
+                    round,
+                    tallies: continuing_tallies,
+                    elected: filled,
+                    eliminated: None,
+                    quota,
+                    keep_values: Some(keep.clone()),
+                    exhausted: Some(exhausted),
+                    tie_break: None,
+                });
+                break;
+            }
+
+            let (_, tied) = lowest_candidates(&continuing_tallies);
+            let (loser, tie_break) = if tied.len() == 1 {
+                (tied[0], None)
+            } else {
+                let (loser, applied) = resolve_tie(&tied, &history, self.tie_strategy);
+                (loser, Some(applied))
+            };
+            eliminated_count += 1;
+            keep.insert(loser, N::zero());
+            history.push(continuing_tallies.clone());
+            trace.push(StvRoundResult {
+                round,
+                tallies: continuing_tallies,
+                elected: Vec::new(),
+                eliminated: Some(loser),
+                quota,
+                keep_values: Some(keep.clone()),
+                exhausted: Some(exhausted),
+                tie_break,
+            });
+        }
+
+        (elected, trace)
+    }
+}
+
+/// Metadata read from a BLT file alongside the ballots themselves, which are
+/// loaded directly into the `Poll` returned by `Poll::from_blt`.
+#[derive(Debug, Clone)]
+pub struct BltMeta {
+    pub seats: usize,
+    pub title: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum BltError<'a> {
+    Io(io::Error),
+    /// The input didn't match the expected BLT token structure: a missing
+    /// header field, an out-of-range candidate number, or a candidate/title
+    /// string that was never closed. Also returned by `Poll::write_blt` when a
+    /// candidate name or the title contains `"`, which BLT cannot represent.
+    Malformed(String),
+    Ballot(BallotError<'a, String>),
+}
+impl<'a> fmt::Display for BltError<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {}", e),
+            Self::Malformed(msg) => write!(f, "Malformed BLT input: {}", msg),
+            Self::Ballot(e) => write!(f, "Invalid ballot: {}", e),
+        }
+    }
+}
+impl<'a> Error for BltError<'a> {}
+impl<'a> From<io::Error> for BltError<'a> {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+enum BltToken {
+    Bare(String),
+    Quoted(String),
+}
+
+/// Split BLT input into whitespace-separated bare tokens (header numbers,
+/// ballot weights and preferences) and double-quoted tokens (candidate names,
+/// the election title).
+fn tokenize_blt(input: &str) -> Result<Vec<BltToken>, BltError<'static>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(i, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '"' {
+            chars.next();
+            let start = i + c.len_utf8();
+            let mut end = None;
+            while let Some(&(j, c2)) = chars.peek() {
+                chars.next();
+                if c2 == '"' {
+                    end = Some(j);
+                    break;
+                }
+            }
+            let end = end.ok_or_else(|| BltError::Malformed("quoted string was never closed".into()))?;
+            tokens.push(BltToken::Quoted(input[start..end].to_string()));
+            continue;
+        }
+        let start = i;
+        let mut end = start;
+        while let Some(&(j, c2)) = chars.peek() {
+            if c2.is_whitespace() || c2 == '"' {
+                break;
+            }
+            end = j + c2.len_utf8();
+            chars.next();
+        }
+        tokens.push(BltToken::Bare(input[start..end].to_string()));
+    }
+    Ok(tokens)
+}
+
+/// A cursor over tokenized BLT input, used to pull the next bare or quoted
+/// token while reporting which field was expected on failure.
+struct BltTokens<'t> {
+    tokens: std::slice::Iter<'t, BltToken>,
+}
+impl<'t> BltTokens<'t> {
+    fn bare(&mut self, what: &str) -> Result<&'t str, BltError<'static>> {
+        match self.tokens.next() {
+            Some(BltToken::Bare(s)) => Ok(s),
+            Some(BltToken::Quoted(_)) => Err(BltError::Malformed(format!("expected {}, found a quoted string", what))),
+            None => Err(BltError::Malformed(format!("unexpected end of input while reading {}", what))),
+        }
+    }
+    fn quoted(&mut self, what: &str) -> Result<&'t str, BltError<'static>> {
+        match self.tokens.next() {
+            Some(BltToken::Quoted(s)) => Ok(s),
+            Some(BltToken::Bare(_)) => Err(BltError::Malformed(format!("expected quoted {}, found a bare token", what))),
+            None => Err(BltError::Malformed(format!("unexpected end of input while reading {}", what))),
+        }
+    }
+}
+
+impl<'a, N: Number> Poll<'a, String, N> {
+    /// Parse a BLT-format election file: a header giving the candidate and
+    /// seat counts, one line per ballot (`weight pref1 pref2 ... 0`), a
+    /// terminating `0`, then each candidate's quoted name (in the order
+    /// referenced by ballot preference numbers) and a quoted election title.
+    ///
+    /// `candidates` is filled with the parsed candidate names and borrowed by
+    /// the returned `Poll`, the same two-step shape as `Poll::new(&HashSet)`.
+    pub fn from_blt<R: Read>(mut reader: R, candidates: &'a mut HashSet<String>) -> Result<(Self, BltMeta), BltError<'a>> {
+        let mut input = String::new();
+        reader.read_to_string(&mut input)?;
+        let tokens = tokenize_blt(&input)?;
+        let mut cursor = BltTokens { tokens: tokens.iter() };
+
+        let num_candidates: usize = cursor.bare("candidate count")?
+            .parse()
+            .map_err(|_| BltError::Malformed("candidate count must be a non-negative integer".into()))?;
+        let seats: usize = cursor.bare("seat count")?
+            .parse()
+            .map_err(|_| BltError::Malformed("seat count must be a non-negative integer".into()))?;
+
+        let mut ballots_raw: Vec<(usize, Vec<usize>)> = Vec::new();
+        loop {
+            let weight: usize = cursor.bare("ballot weight")?
+                .parse()
+                .map_err(|_| BltError::Malformed("ballot weight must be a non-negative integer".into()))?;
+            if weight == 0 {
+                break;
+            }
+            let mut prefs = Vec::new();
+            loop {
+                let pref: usize = cursor.bare("ballot preference")?
+                    .parse()
+                    .map_err(|_| BltError::Malformed("ballot preference must be a non-negative integer".into()))?;
+                if pref == 0 {
+                    break;
+                }
+                if pref > num_candidates {
+                    return Err(BltError::Malformed(format!("preference {} is out of range for {} candidates", pref, num_candidates)));
+                }
+                prefs.push(pref - 1);
+            }
+            ballots_raw.push((weight, prefs));
+        }
+
+        let mut names = Vec::with_capacity(num_candidates);
+        for _ in 0..num_candidates {
+            names.push(cursor.quoted("candidate name")?.to_string());
+        }
+        let title = cursor.quoted("election title").ok().map(|s| s.to_string());
+
+        candidates.clear();
+        candidates.extend(names.iter().cloned());
+        let candidates: &'a HashSet<String> = candidates;
+
+        let mut poll = Poll::new(candidates).allow_truncated(true);
+        for (weight, pref_indices) in ballots_raw {
+            let prefs: Vec<&'a String> = pref_indices.iter()
+                .map(|&i| candidates.get(&names[i]).unwrap())
+                .collect();
+            poll.add_ballot(prefs, Some(N::from_usize(weight))).map_err(BltError::Ballot)?;
+        }
+
+        Ok((poll, BltMeta { seats, title }))
+    }
+
+    /// Serialize the poll as a BLT-format election file with `seats` seats
+    /// and `title` as the election title.
+    ///
+    /// Candidates are written in a fixed order (sorted by name) rather than
+    /// `index_candidates`'s order, which only reflects the backing
+    /// `HashSet`'s iteration and can otherwise change between runs over the
+    /// same poll. Preference numbers are remapped to match.
+    pub fn write_blt<W: Write>(&self, mut writer: W, seats: usize, title: &str) -> Result<(), BltError<'a>>
+    where
+        N: fmt::Display,
+    {
+        if title.contains('"') || self.index_candidates.iter().any(|name| name.contains('"')) {
+            return Err(BltError::Malformed("candidate names and the election title cannot contain '\"': BLT has no escape syntax".into()));
+        }
+
+        let mut order: Vec<CandidateIndex> = (0..self.index_candidates.len()).collect();
+        order.sort_by(|&a, &b| self.index_candidates[a].cmp(self.index_candidates[b]));
+        let mut blt_number = vec![0; order.len()];
+        for (position, &old_index) in order.iter().enumerate() {
+            blt_number[old_index] = position + 1;
+        }
+
+        writeln!(writer, "{} {}", order.len(), seats)?;
+        for form in &self.ballots {
+            write!(writer, "{}", form.weight)?;
+            for &i in &form.prefs[form.active..] {
+                write!(writer, " {}", blt_number[i])?;
+            }
+            writeln!(writer, " 0")?;
+        }
+        writeln!(writer, "0")?;
+        for &old_index in &order {
+            writeln!(writer, "\"{}\"", self.index_candidates[old_index])?;
+        }
+        writeln!(writer, "\"{}\"", title)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stv_gregory_known_result() {
+        let mut candidates: HashSet<String> = HashSet::new();
+        candidates.insert("A".to_string());
+        candidates.insert("B".to_string());
+        candidates.insert("C".to_string());
+
+        let mut poll: Poll<String> = Poll::new(&candidates).allow_truncated(true);
+        let a = candidates.get("A").unwrap();
+        let b = candidates.get("B").unwrap();
+        let c = candidates.get("C").unwrap();
+        poll.add_ballot(vec![a, b], Some(3.0)).unwrap();
+        poll.add_ballot(vec![b, c], Some(2.0)).unwrap();
+        poll.add_ballot(vec![c, a], Some(1.0)).unwrap();
+
+        let (elected, trace) = poll.count_stv(1, StvMethod::Gregory(StvTransferMethod::WeightedInclusiveGregory));
+
+        assert_eq!(elected, vec![a]);
+        assert_eq!(trace.len(), 2);
+        assert_eq!(trace[0].eliminated, Some(c));
+    }
+
+    #[test]
+    fn stv_meek_converges_on_quota() {
+        let mut candidates: HashSet<String> = HashSet::new();
+        candidates.insert("A".to_string());
+        candidates.insert("B".to_string());
+        candidates.insert("C".to_string());
+
+        let mut poll: Poll<String> = Poll::new(&candidates).allow_truncated(true);
+        let a = candidates.get("A").unwrap();
+        let b = candidates.get("B").unwrap();
+        let c = candidates.get("C").unwrap();
+        poll.add_ballot(vec![a, b], Some(9.0)).unwrap();
+        poll.add_ballot(vec![b], Some(3.0)).unwrap();
+        poll.add_ballot(vec![c], Some(2.0)).unwrap();
+
+        let tolerance = 0.0001;
+        let (elected, trace) = poll.count_stv(2, StvMethod::Meek { tolerance });
+
+        let elected_set: HashSet<&str> = elected.iter().map(|s| s.as_str()).collect();
+        assert_eq!(elected_set, HashSet::from(["A", "B"]));
+
+        // A is elected in round 1 holding a surplus over quota; by the next
+        // round its keep value has been adjusted down so that `keep * votes`
+        // converges on the quota, within `tolerance`.
+        let keep_after = trace[1].keep_values.as_ref().unwrap();
+        let a_keep = *keep_after.get(a).unwrap();
+        assert!((a_keep * 9.0 - trace[0].quota).abs() <= tolerance);
+    }
+
+    #[test]
+    fn blt_round_trip_preserves_ballots_and_is_stable() {
+        let input = b"3 2\n2 1 2 0\n1 2 3 0\n1 3 0\n0\n\"Carol\"\n\"Alice\"\n\"Bob\"\n\"Sample Election\"\n";
+
+        let mut candidates: HashSet<String> = HashSet::new();
+        let (poll, meta): (Poll<String, f64>, BltMeta) = Poll::from_blt(&input[..], &mut candidates).unwrap();
+        assert_eq!(meta.seats, 2);
+        assert_eq!(meta.title.as_deref(), Some("Sample Election"));
+
+        let mut first = Vec::new();
+        poll.write_blt(&mut first, meta.seats, meta.title.as_deref().unwrap_or("")).unwrap();
+        let mut second = Vec::new();
+        poll.write_blt(&mut second, meta.seats, meta.title.as_deref().unwrap_or("")).unwrap();
+        // Candidate order comes from a sort in `write_blt`, not `HashSet`
+        // iteration, so re-serializing the same poll is reproducible.
+        assert_eq!(first, second);
+
+        let mut roundtrip_candidates: HashSet<String> = HashSet::new();
+        let (roundtrip_poll, roundtrip_meta): (Poll<String, f64>, BltMeta) = Poll::from_blt(&first[..], &mut roundtrip_candidates).unwrap();
+        assert_eq!(roundtrip_meta.seats, meta.seats);
+        assert_eq!(roundtrip_poll.ballots.len(), poll.ballots.len());
+    }
+}
@@ -1,5 +1,5 @@
 use std::collections::HashSet;
-use rcir::Poll;
+use rcir::{Poll, RoundOutcome};
 
 fn main() {
     let mut candidates: HashSet<String> = HashSet::new();
@@ -11,19 +11,25 @@ fn main() {
     for i in 0..num_candidates {
         candidates.insert(format!("Person {}", i));
     }
-    let mut poll = Poll::new(&candidates);
+    let mut poll: Poll<String> = Poll::new(&candidates);
     
     println!("Generating {} ballots...", num_ballots);
     for _ in 0..num_ballots {
         let ballot = poll.generate_ballot();
-        poll.add_ballot(ballot).unwrap();
+        poll.add_ballot(ballot, None).unwrap();
     }
     
     println!("Calculating results...");
-    let mut winner = None;
-    for result in poll.start_rounds() {
-        println!("Round {}: {} ({} votes)", result.round, result.loser, result.votes);
-        winner = Some(result.loser);
+    let (winner, trace) = poll.winner();
+    for outcome in &trace {
+        match outcome {
+            RoundOutcome::Eliminated(result) => {
+                println!("Round {}: {} eliminated ({} votes, {} exhausted)", result.round, result.loser, result.votes, result.exhausted);
+            },
+            RoundOutcome::Winner { candidate, votes, round } => {
+                println!("Round {}: {} wins a majority ({} votes)", round, candidate, votes);
+            },
+        }
     }
     println!("Election winner: {}!", winner.unwrap());
 }